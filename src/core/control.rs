@@ -0,0 +1,273 @@
+//! Local control socket that lets other processes on the same machine drive a
+//! session without going through the Tauri frontend.
+//!
+//! Binds a Unix domain socket on Linux/macOS (a named pipe on Windows) and
+//! accepts newline-delimited JSON commands, forwarding the same
+//! `session-message` payloads normally emitted to the frontend back to every
+//! connected client as JSON lines.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::broadcast;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, warn};
+
+use crate::core::session::{SessionMessage, SessionState};
+
+/// Commands accepted as newline-delimited JSON on the control socket.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum ControlCommand {
+    SendText { content: String },
+    OfferFile { path: String },
+    Status,
+}
+
+/// A running local control socket, bound for the lifetime of a session.
+pub struct ControlSocket {
+    accept_task: JoinHandle<()>,
+    #[cfg(unix)]
+    socket_path: std::path::PathBuf,
+}
+
+impl ControlSocket {
+    /// Bind a control socket/pipe named after `name` and start servicing
+    /// commands against `state`.
+    pub async fn start(state: Arc<Mutex<SessionState>>, name: &str) -> Result<Self> {
+        #[cfg(unix)]
+        {
+            Self::start_unix(state, name).await
+        }
+        #[cfg(windows)]
+        {
+            Self::start_windows(state, name).await
+        }
+    }
+
+    #[cfg(unix)]
+    async fn start_unix(state: Arc<Mutex<SessionState>>, name: &str) -> Result<Self> {
+        let socket_path = std::env::temp_dir().join(format!("sendme-session-{name}.sock"));
+        let _ = std::fs::remove_file(&socket_path);
+
+        let listener = tokio::net::UnixListener::bind(&socket_path)
+            .with_context(|| format!("Failed to bind control socket at {socket_path:?}"))?;
+
+        // Restrict the socket to the current user: it accepts unauthenticated
+        // `send_text`/`offer_file` commands and relays every `session-message`,
+        // so on a shared machine leaving it at the umask default would let any
+        // other local user drive the session or read its traffic.
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&socket_path, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to set permissions on {socket_path:?}"))?;
+        }
+
+        debug!("Control socket listening at {:?}", socket_path);
+
+        let accept_task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _addr)) => {
+                        let state = state.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_client(state, stream).await {
+                                warn!("Control socket client error: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        error!("Control socket accept error: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            accept_task,
+            socket_path,
+        })
+    }
+
+    #[cfg(windows)]
+    async fn start_windows(state: Arc<Mutex<SessionState>>, name: &str) -> Result<Self> {
+        use tokio::net::windows::named_pipe::ServerOptions;
+
+        let pipe_name = format!(r"\\.\pipe\sendme-session-{name}");
+        let first_server = ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(&pipe_name)
+            .with_context(|| format!("Failed to create control pipe {pipe_name}"))?;
+
+        let accept_task = tokio::spawn(async move {
+            let mut server = first_server;
+            loop {
+                if let Err(e) = server.connect().await {
+                    error!("Control pipe connect error: {}", e);
+                    break;
+                }
+
+                let connected = server;
+                server = match ServerOptions::new().create(&pipe_name) {
+                    Ok(next) => next,
+                    Err(e) => {
+                        error!("Failed to create control pipe: {}", e);
+                        let state = state.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_client(state, connected).await {
+                                warn!("Control socket client error: {}", e);
+                            }
+                        });
+                        break;
+                    }
+                };
+
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_client(state, connected).await {
+                        warn!("Control socket client error: {}", e);
+                    }
+                });
+            }
+        });
+
+        Ok(Self { accept_task })
+    }
+
+    /// Stop accepting new connections and remove the socket/pipe from disk.
+    pub fn shutdown(&self) {
+        self.accept_task.abort();
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+impl Drop for ControlSocket {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+async fn handle_client<S>(state: Arc<Mutex<SessionState>>, stream: S) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (read_half, mut write_half) = tokio::io::split(stream);
+    let mut lines = BufReader::new(read_half).lines();
+    let mut events = state.lock().await.subscribe_events();
+
+    loop {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line.context("Failed to read control command")? else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                let response = match serde_json::from_str::<ControlCommand>(&line) {
+                    Ok(command) => dispatch_command(&state, command).await,
+                    Err(e) => serde_json::json!({ "ok": false, "error": e.to_string() }).to_string(),
+                };
+                write_line(&mut write_half, &response).await?;
+            }
+            event = events.recv() => {
+                match event {
+                    Ok(payload) => write_line(&mut write_half, &payload).await?,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Control socket client lagged, dropped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn dispatch_command(state: &Arc<Mutex<SessionState>>, command: ControlCommand) -> String {
+    match command {
+        ControlCommand::SendText { content } => {
+            let result = state
+                .lock()
+                .await
+                .send_message(SessionMessage::Text { content })
+                .await;
+            ok_or_error(result)
+        }
+        ControlCommand::OfferFile { path } => ok_or_error(offer_file(state, &path).await),
+        ControlCommand::Status => {
+            let state = state.lock().await;
+            serde_json::json!({
+                "type": "status",
+                "connected": state.connection.is_some(),
+                "peer_id": state.peer_id.map(|id| id.to_string()),
+            })
+            .to_string()
+        }
+    }
+}
+
+fn ok_or_error(result: Result<()>) -> String {
+    match result {
+        Ok(()) => serde_json::json!({ "ok": true }).to_string(),
+        Err(e) => serde_json::json!({ "ok": false, "error": e.to_string() }).to_string(),
+    }
+}
+
+/// Hash and offer a local file over the session, the same way a `FileOffer`
+/// triggered from the frontend would be sent.
+async fn offer_file(state: &Arc<Mutex<SessionState>>, path: &str) -> Result<()> {
+    let mut file = tokio::fs::File::open(path)
+        .await
+        .with_context(|| format!("Failed to open file {path}"))?;
+    let size = file.metadata().await.context("Failed to stat file")?.len();
+
+    let mut hasher = blake3::Hasher::new();
+    let mut chunk = vec![0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut chunk)
+            .await
+            .context("Failed to read file")?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&chunk[..n]);
+    }
+
+    let name = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string());
+
+    state
+        .lock()
+        .await
+        .send_message(SessionMessage::FileOffer {
+            name,
+            size,
+            hash: hasher.finalize().to_hex().to_string(),
+        })
+        .await
+}
+
+async fn write_line<W: tokio::io::AsyncWrite + Unpin>(writer: &mut W, line: &str) -> Result<()> {
+    writer
+        .write_all(line.as_bytes())
+        .await
+        .context("Failed to write control response")?;
+    writer
+        .write_all(b"\n")
+        .await
+        .context("Failed to write control response")?;
+    writer
+        .flush()
+        .await
+        .context("Failed to flush control response")
+}
@@ -0,0 +1,10 @@
+pub mod receive;
+pub mod send;
+pub mod session;
+pub mod types;
+
+#[cfg(feature = "control-socket")]
+pub mod control;
+
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
@@ -1,10 +1,18 @@
 use anyhow::{Context, Result};
+use bytes::Bytes;
+use futures::Stream;
 use iroh::protocol::{Handler, ProtocolHandler};
 use iroh_blobs::util::RpcError;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::sync::Mutex;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{broadcast, oneshot, Mutex};
 use tracing::{debug, error, info, warn};
 
 use crate::core::types::AppHandle;
@@ -12,6 +20,26 @@ use crate::core::types::AppHandle;
 /// ALPN identifier for the session protocol
 pub const SESSION_ALPN: &[u8] = b"sendme/session/1";
 
+/// Maximum size of the JSON `SessionMessage` header. Bodies streamed after
+/// the header are not subject to this limit.
+const MAX_HEADER_SIZE: usize = 1024 * 1024; // 1 MiB
+
+/// Size of each frame written by [`SessionState::send_message_with_body`].
+const BODY_FRAME_SIZE: usize = 64 * 1024; // 64 KiB
+
+/// Default time to wait for a response before [`SessionState::send_request`]
+/// gives up and drops its entry from the correlation map.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often a `Ping` is sent to detect half-open connections.
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Backoff before the first reconnect attempt, doubled after each failure.
+const INITIAL_RECONNECT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound the reconnect backoff is capped at.
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+
 /// Messages exchanged in a session
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SessionMessage {
@@ -27,6 +55,10 @@ pub enum SessionMessage {
     FileAccept { hash: String },
     /// WebRTC signaling data
     CallSignal { signal_type: String, data: String },
+    /// Keepalive probe; the receiver replies with `Pong`
+    Ping,
+    /// Reply to a `Ping`, confirming the connection is still alive
+    Pong,
 }
 
 impl SessionMessage {
@@ -41,23 +73,172 @@ impl SessionMessage {
     }
 }
 
+/// How an [`Envelope`] should be handled by the peer that receives it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum EnvelopeKind {
+    /// Expects a matching `Response` envelope with the same id
+    Request,
+    /// Answers a previously received `Request` envelope
+    Response,
+    /// Fire-and-forget; no reply is expected
+    Notify,
+}
+
+/// Wire envelope carrying every `SessionMessage`, correlating requests with
+/// their responses via `id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Envelope {
+    pub id: u64,
+    pub kind: EnvelopeKind,
+    pub body: SessionMessage,
+    /// Hex trace id of the span active when this envelope was sent, present
+    /// only when the `telemetry` feature is enabled, for linking the
+    /// receiver's span to the sender's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+}
+
+impl Envelope {
+    /// Build an envelope, capturing the current span's trace context when
+    /// the `telemetry` feature is enabled so the receiver can link to it.
+    fn new(id: u64, kind: EnvelopeKind, body: SessionMessage) -> Self {
+        #[cfg(feature = "telemetry")]
+        let (trace_id, span_id) = crate::core::telemetry::current_trace_context();
+        #[cfg(not(feature = "telemetry"))]
+        let (trace_id, span_id) = (None, None);
+
+        Self {
+            id,
+            kind,
+            body,
+            trace_id,
+            span_id,
+        }
+    }
+
+    /// Serialize envelope to bytes
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).context("Failed to serialize envelope")
+    }
+
+    /// Deserialize envelope from bytes
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        serde_json::from_slice(bytes).context("Failed to deserialize envelope")
+    }
+}
+
+/// Produces the `Response` body for an incoming `Request` envelope, e.g.
+/// deciding whether to accept a `FileOffer`. Installed via
+/// [`SessionState::set_request_handler`].
+pub type RequestHandler = Arc<dyn Fn(&SessionMessage) -> SessionMessage + Send + Sync>;
+
+/// Receives a message's streamed body as it arrives, e.g. to write real file
+/// contents to disk. Installed via [`SessionState::set_body_handler`].
+pub type BodyHandler = Arc<dyn Fn(SessionMessage, BodyStream) + Send + Sync>;
+
 /// Session state shared between handler and commands
 pub struct SessionState {
     pub app_handle: AppHandle,
     pub peer_id: Option<iroh::NodeId>,
     pub connection: Option<Arc<Mutex<iroh::endpoint::Connection>>>,
+    /// Set by `close()` to stop the reconnect/keepalive loops for good.
+    closing: Arc<std::sync::atomic::AtomicBool>,
+    /// Tells `run_stream_loop` to stop accepting new streams, so `close()`
+    /// can wait for in-flight ones to finish instead of cutting them off.
+    close_notify: Arc<tokio::sync::Notify>,
+    /// Per-stream handler tasks spawned by `run_stream_loop`, drained by
+    /// `close()` before the connection itself is closed.
+    stream_tasks: Arc<Mutex<tokio::task::JoinSet<()>>>,
+    next_envelope_id: Arc<AtomicU64>,
+    pending_requests: Arc<Mutex<HashMap<u64, oneshot::Sender<SessionMessage>>>>,
+    event_tx: broadcast::Sender<String>,
+    request_handler: Option<RequestHandler>,
+    body_handler: Option<BodyHandler>,
+    #[cfg(feature = "control-socket")]
+    control_socket: Option<crate::core::control::ControlSocket>,
 }
 
 impl SessionState {
     pub fn new(app_handle: AppHandle) -> Self {
+        let (event_tx, _) = broadcast::channel(256);
         Self {
             app_handle,
             peer_id: None,
             connection: None,
+            closing: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            close_notify: Arc::new(tokio::sync::Notify::new()),
+            stream_tasks: Arc::new(Mutex::new(tokio::task::JoinSet::new())),
+            next_envelope_id: Arc::new(AtomicU64::new(0)),
+            pending_requests: Arc::new(Mutex::new(HashMap::new())),
+            event_tx,
+            request_handler: None,
+            body_handler: None,
+            #[cfg(feature = "control-socket")]
+            control_socket: None,
         }
     }
 
+    /// Install a callback that produces the `Response` body for an incoming
+    /// `Request` envelope. With no handler installed, requests are echoed
+    /// straight back, which is rarely what the peer expects.
+    pub fn set_request_handler(
+        &mut self,
+        handler: impl Fn(&SessionMessage) -> SessionMessage + Send + Sync + 'static,
+    ) {
+        self.request_handler = Some(Arc::new(handler));
+    }
+
+    /// Install a callback that receives a message's streamed body as it
+    /// arrives. With no handler installed, bodies are read off the wire and
+    /// discarded.
+    pub fn set_body_handler(
+        &mut self,
+        handler: impl Fn(SessionMessage, BodyStream) + Send + Sync + 'static,
+    ) {
+        self.body_handler = Some(Arc::new(handler));
+    }
+
+    /// Gracefully close the session: stop accepting new streams, wait for
+    /// in-flight ones (e.g. a body mid-transfer via
+    /// [`Self::send_message_with_body`]/[`BodyStream`]) to finish, stop the
+    /// keepalive/reconnect loop, and only then close the underlying
+    /// connection.
+    pub async fn close(&mut self) {
+        self.closing.store(true, Ordering::SeqCst);
+        self.close_notify.notify_waiters();
+
+        let mut stream_tasks = self.stream_tasks.lock().await;
+        while stream_tasks.join_next().await.is_some() {}
+        drop(stream_tasks);
+
+        if let Some(conn) = self.connection.take() {
+            conn.lock().await.close(0u32.into(), b"session closed");
+        }
+
+        #[cfg(feature = "control-socket")]
+        if let Some(socket) = self.control_socket.take() {
+            socket.shutdown();
+        }
+    }
+
+    /// Subscribe to the same `session-message` payloads forwarded to the
+    /// frontend, e.g. for the local control socket.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<String> {
+        self.event_tx.subscribe()
+    }
+
+    /// Allocate the next monotonically increasing envelope id
+    fn next_id(&self) -> u64 {
+        self.next_envelope_id.fetch_add(1, Ordering::SeqCst)
+    }
+
     /// Send a message to the peer
+    #[cfg_attr(
+        feature = "telemetry",
+        tracing::instrument(skip(self, message), fields(kind = ?message))
+    )]
     pub async fn send_message(&self, message: SessionMessage) -> Result<()> {
         let conn = self
             .connection
@@ -71,62 +252,208 @@ impl SessionState {
             .await
             .context("Failed to open send stream")?;
 
-        let bytes = message.to_bytes()?;
-        let len = bytes.len() as u32;
+        #[cfg(feature = "telemetry")]
+        let message_bytes = message.to_bytes()?.len() as u64;
 
-        // Send length prefix
-        send_stream
-            .write_all(&len.to_be_bytes())
-            .await
-            .context("Failed to write message length")?;
+        let envelope = Envelope::new(self.next_id(), EnvelopeKind::Notify, message);
+        Self::write_header(&mut send_stream, &envelope).await?;
 
-        // Send message
+        // No body follows: terminate the body region immediately
         send_stream
-            .write_all(&bytes)
+            .write_all(&0u32.to_be_bytes())
             .await
-            .context("Failed to write message")?;
+            .context("Failed to write body terminator")?;
 
         send_stream
             .finish()
             .await
             .context("Failed to finish stream")?;
 
-        debug!("Sent session message: {:?}", message);
+        #[cfg(feature = "telemetry")]
+        crate::core::telemetry::record_sent(&envelope.body, message_bytes);
+
+        debug!("Sent session message: {:?}", envelope.body);
         Ok(())
     }
 
-    /// Emit event to frontend
-    fn emit_event(&self, event_name: &str, payload: &str) {
-        if let Some(handle) = &self.app_handle {
-            if let Err(e) = handle.emit_event_with_payload(event_name, payload) {
-                warn!("Failed to emit event {}: {}", event_name, e);
+    /// Send a message followed by a streamed body, for payloads too large to
+    /// buffer in memory (e.g. real file contents instead of a blobs round-trip).
+    ///
+    /// `body` is copied in `BODY_FRAME_SIZE` frames, each `[4-byte big-endian
+    /// length][payload]`, flushing after every frame so QUIC flow control
+    /// applies backpressure instead of the sender racing ahead. A zero-length
+    /// frame terminates the body region.
+    #[cfg_attr(
+        feature = "telemetry",
+        tracing::instrument(skip(self, message, body), fields(kind = ?message))
+    )]
+    pub async fn send_message_with_body(
+        &self,
+        message: SessionMessage,
+        mut body: impl AsyncRead + Unpin,
+    ) -> Result<()> {
+        let conn = self
+            .connection
+            .as_ref()
+            .context("No active connection")?
+            .lock()
+            .await;
+
+        let mut send_stream = conn
+            .open_uni()
+            .await
+            .context("Failed to open send stream")?;
+
+        #[cfg(feature = "telemetry")]
+        let header_bytes = message.to_bytes()?.len() as u64;
+
+        let envelope = Envelope::new(self.next_id(), EnvelopeKind::Notify, message);
+        Self::write_header(&mut send_stream, &envelope).await?;
+
+        #[cfg(feature = "telemetry")]
+        let mut body_bytes = 0u64;
+
+        let mut chunk = vec![0u8; BODY_FRAME_SIZE];
+        loop {
+            let n = body
+                .read(&mut chunk)
+                .await
+                .context("Failed to read body chunk")?;
+            if n == 0 {
+                break;
+            }
+
+            send_stream
+                .write_all(&(n as u32).to_be_bytes())
+                .await
+                .context("Failed to write body frame length")?;
+            send_stream
+                .write_all(&chunk[..n])
+                .await
+                .context("Failed to write body frame")?;
+            send_stream
+                .flush()
+                .await
+                .context("Failed to flush body frame")?;
+
+            #[cfg(feature = "telemetry")]
+            {
+                body_bytes += n as u64;
             }
         }
+
+        send_stream
+            .write_all(&0u32.to_be_bytes())
+            .await
+            .context("Failed to write body terminator")?;
+
+        send_stream
+            .finish()
+            .await
+            .context("Failed to finish stream")?;
+
+        #[cfg(feature = "telemetry")]
+        crate::core::telemetry::record_sent(&envelope.body, header_bytes + body_bytes);
+
+        debug!("Sent session message with body: {:?}", envelope.body);
+        Ok(())
     }
-}
 
-/// Session protocol handler
-pub struct SessionHandler {
-    state: Arc<Mutex<SessionState>>,
-}
+    /// Send a message and await the peer's reply on a dedicated bidirectional
+    /// stream, using the default request timeout.
+    pub async fn send_request(&self, message: SessionMessage) -> Result<SessionMessage> {
+        self.send_request_timeout(message, DEFAULT_REQUEST_TIMEOUT)
+            .await
+    }
 
-impl SessionHandler {
-    pub fn new(app_handle: AppHandle) -> Self {
-        Self {
-            state: Arc::new(Mutex::new(SessionState::new(app_handle))),
+    /// Like [`Self::send_request`], with a caller-supplied timeout.
+    pub async fn send_request_timeout(
+        &self,
+        message: SessionMessage,
+        timeout: Duration,
+    ) -> Result<SessionMessage> {
+        let id = self.next_id();
+        let envelope = Envelope::new(id, EnvelopeKind::Request, message);
+
+        let conn = self
+            .connection
+            .as_ref()
+            .context("No active connection")?
+            .lock()
+            .await;
+
+        let (mut send_stream, mut recv_stream) = conn
+            .open_bi()
+            .await
+            .context("Failed to open request stream")?;
+        drop(conn);
+
+        Self::write_header(&mut send_stream, &envelope).await?;
+
+        // No body follows: terminate the body region immediately, matching
+        // `send_message`/`send_message_with_body`. `handle_bi_stream` always
+        // drains a body region after the header, so skipping this would leave
+        // it blocked reading a frame length that never arrives.
+        send_stream
+            .write_all(&0u32.to_be_bytes())
+            .await
+            .context("Failed to write body terminator")?;
+
+        send_stream
+            .finish()
+            .await
+            .context("Failed to finish request stream")?;
+
+        let (tx, rx) = oneshot::channel();
+        self.pending_requests.lock().await.insert(id, tx);
+
+        let pending_requests = self.pending_requests.clone();
+        tokio::spawn(async move {
+            match Self::read_envelope(&mut recv_stream).await {
+                Ok(response) => {
+                    if let Some(tx) = pending_requests.lock().await.remove(&response.id) {
+                        let _ = tx.send(response.body);
+                    }
+                }
+                Err(e) => {
+                    warn!("Failed to read response for request {}: {}", id, e);
+                    pending_requests.lock().await.remove(&id);
+                }
+            }
+        });
+
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => anyhow::bail!("Request {} was dropped before a response arrived", id),
+            Err(_) => {
+                self.pending_requests.lock().await.remove(&id);
+                anyhow::bail!("Request {} timed out waiting for a response", id)
+            }
         }
     }
 
-    pub fn get_state(&self) -> Arc<Mutex<SessionState>> {
-        self.state.clone()
+    /// Write the length-prefixed JSON header shared by every outgoing stream.
+    async fn write_header(
+        send_stream: &mut iroh::endpoint::SendStream,
+        envelope: &Envelope,
+    ) -> Result<()> {
+        let bytes = envelope.to_bytes()?;
+        let len = bytes.len() as u32;
+
+        send_stream
+            .write_all(&len.to_be_bytes())
+            .await
+            .context("Failed to write message length")?;
+        send_stream
+            .write_all(&bytes)
+            .await
+            .context("Failed to write message")?;
+
+        Ok(())
     }
 
-    /// Handle incoming unidirectional stream
-    async fn handle_uni_stream(
-        state: Arc<Mutex<SessionState>>,
-        mut recv_stream: iroh::endpoint::RecvStream,
-    ) -> Result<()> {
-        // Read length prefix
+    /// Read the length-prefixed JSON header from a stream into an `Envelope`.
+    async fn read_envelope(recv_stream: &mut iroh::endpoint::RecvStream) -> Result<Envelope> {
         let mut len_bytes = [0u8; 4];
         recv_stream
             .read_exact(&mut len_bytes)
@@ -134,32 +461,30 @@ impl SessionHandler {
             .context("Failed to read message length")?;
         let len = u32::from_be_bytes(len_bytes) as usize;
 
-        // Validate length
-        if len > 10 * 1024 * 1024 {
-            // 10MB max
-            anyhow::bail!("Message too large: {} bytes", len);
+        if len > MAX_HEADER_SIZE {
+            anyhow::bail!("Message header too large: {} bytes", len);
         }
 
-        // Read message
         let mut buffer = vec![0u8; len];
         recv_stream
             .read_exact(&mut buffer)
             .await
             .context("Failed to read message")?;
 
-        let message = SessionMessage::from_bytes(&buffer)?;
-        debug!("Received session message: {:?}", message);
+        Envelope::from_bytes(&buffer)
+    }
 
-        // Handle message
-        let state_lock = state.lock().await;
-        match &message {
+    /// Handle a received message the way every `Notify`/`Request` body is
+    /// handled: forward it to the frontend as a `session-message` event.
+    fn dispatch(&self, message: &SessionMessage) {
+        match message {
             SessionMessage::Text { content } => {
                 let payload = serde_json::json!({
                     "type": "text",
                     "content": content
                 })
                 .to_string();
-                state_lock.emit_event("session-message", &payload);
+                self.emit_event("session-message", &payload);
             }
             SessionMessage::FileOffer { name, size, hash } => {
                 let payload = serde_json::json!({
@@ -169,7 +494,7 @@ impl SessionHandler {
                     "hash": hash
                 })
                 .to_string();
-                state_lock.emit_event("session-message", &payload);
+                self.emit_event("session-message", &payload);
             }
             SessionMessage::FileAccept { hash } => {
                 let payload = serde_json::json!({
@@ -177,7 +502,7 @@ impl SessionHandler {
                     "hash": hash
                 })
                 .to_string();
-                state_lock.emit_event("session-message", &payload);
+                self.emit_event("session-message", &payload);
             }
             SessionMessage::CallSignal { signal_type, data } => {
                 let payload = serde_json::json!({
@@ -186,7 +511,269 @@ impl SessionHandler {
                     "data": data
                 })
                 .to_string();
-                state_lock.emit_event("session-message", &payload);
+                self.emit_event("session-message", &payload);
+            }
+            // Keepalive traffic is handled before `dispatch` is reached; a stray
+            // Ping/Pong here (e.g. delivered over a bidirectional stream) is just noise.
+            SessionMessage::Ping | SessionMessage::Pong => {}
+        }
+    }
+
+    /// Emit a connection-lifecycle event (`session-connected`,
+    /// `session-reconnecting`, `session-disconnected`) to the frontend
+    fn emit_status(&self, event_name: &str) {
+        if let Some(handle) = &self.app_handle {
+            let _ = handle.emit_event(event_name);
+        }
+    }
+
+    /// Emit event to frontend
+    fn emit_event(&self, event_name: &str, payload: &str) {
+        if event_name == "session-message" {
+            // Ignore send errors: no local control socket client is listening
+            let _ = self.event_tx.send(payload.to_string());
+        }
+
+        if let Some(handle) = &self.app_handle {
+            if let Err(e) = handle.emit_event_with_payload(event_name, payload) {
+                warn!("Failed to emit event {}: {}", event_name, e);
+            }
+        }
+    }
+}
+
+/// Streams the body frames that optionally follow a `SessionMessage` header.
+///
+/// Frames are `[4-byte big-endian length][payload]`, terminated by a
+/// zero-length frame. Unlike the header, frame payloads are not length
+/// limited, so arbitrarily large bodies can be relayed without buffering.
+pub struct BodyStream {
+    state: BodyStreamState,
+}
+
+type ReadResult = (iroh::endpoint::RecvStream, Result<Option<Bytes>>);
+
+enum BodyStreamState {
+    Reading(Pin<Box<dyn Future<Output = ReadResult> + Send>>),
+    Done,
+}
+
+impl BodyStream {
+    pub(crate) fn new(recv_stream: iroh::endpoint::RecvStream) -> Self {
+        Self {
+            state: BodyStreamState::Reading(Box::pin(Self::read_next_frame(recv_stream))),
+        }
+    }
+
+    async fn read_next_frame(mut recv_stream: iroh::endpoint::RecvStream) -> ReadResult {
+        let result = async {
+            let mut len_bytes = [0u8; 4];
+            recv_stream
+                .read_exact(&mut len_bytes)
+                .await
+                .context("Failed to read body frame length")?;
+            let len = u32::from_be_bytes(len_bytes) as usize;
+
+            if len == 0 {
+                return Ok(None);
+            }
+
+            let mut payload = vec![0u8; len];
+            recv_stream
+                .read_exact(&mut payload)
+                .await
+                .context("Failed to read body frame payload")?;
+
+            Ok(Some(Bytes::from(payload)))
+        }
+        .await;
+
+        (recv_stream, result)
+    }
+
+    /// Read and discard every remaining frame, for callers that only care
+    /// about the header (e.g. message kinds that never carry a body).
+    /// Returns the total number of body bytes discarded.
+    async fn drain(mut self) -> Result<u64> {
+        let mut bytes = 0u64;
+        while let Some(frame) = futures::StreamExt::next(&mut self).await {
+            bytes += frame?.len() as u64;
+        }
+        Ok(bytes)
+    }
+}
+
+impl Stream for BodyStream {
+    type Item = Result<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        match &mut self.state {
+            BodyStreamState::Done => Poll::Ready(None),
+            BodyStreamState::Reading(fut) => match fut.as_mut().poll(cx) {
+                Poll::Pending => Poll::Pending,
+                Poll::Ready((recv_stream, Ok(Some(bytes)))) => {
+                    self.state = BodyStreamState::Reading(Box::pin(Self::read_next_frame(recv_stream)));
+                    Poll::Ready(Some(Ok(bytes)))
+                }
+                Poll::Ready((_, Ok(None))) => {
+                    self.state = BodyStreamState::Done;
+                    Poll::Ready(None)
+                }
+                Poll::Ready((_, Err(e))) => {
+                    self.state = BodyStreamState::Done;
+                    Poll::Ready(Some(Err(e)))
+                }
+            },
+        }
+    }
+}
+
+/// Session protocol handler
+pub struct SessionHandler {
+    state: Arc<Mutex<SessionState>>,
+}
+
+impl SessionHandler {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SessionState::new(app_handle))),
+        }
+    }
+
+    pub fn get_state(&self) -> Arc<Mutex<SessionState>> {
+        self.state.clone()
+    }
+
+    /// Handle incoming unidirectional stream
+    async fn handle_uni_stream(
+        state: Arc<Mutex<SessionState>>,
+        mut recv_stream: iroh::endpoint::RecvStream,
+    ) -> Result<()> {
+        #[cfg(feature = "telemetry")]
+        let started_at = std::time::Instant::now();
+
+        let envelope = SessionState::read_envelope(&mut recv_stream).await?;
+        debug!("Received session message: {:?}", envelope.body);
+
+        // Keepalive traffic never reaches the frontend or the body handler:
+        // reply to a Ping with a Pong and just note that a Pong arrived.
+        // Neither ever carries a body, so drain the (empty) frame region
+        // directly instead of going through `deliver_body`.
+        match &envelope.body {
+            SessionMessage::Ping => {
+                BodyStream::new(recv_stream)
+                    .drain()
+                    .await
+                    .context("Failed to drain ping body")?;
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = state.lock().await.send_message(SessionMessage::Pong).await {
+                        warn!("Failed to send keepalive pong: {}", e);
+                    }
+                });
+                return Ok(());
+            }
+            SessionMessage::Pong => {
+                debug!("Received keepalive pong");
+                BodyStream::new(recv_stream)
+                    .drain()
+                    .await
+                    .context("Failed to drain pong body")?;
+                return Ok(());
+            }
+            _ => {}
+        }
+
+        #[cfg(feature = "telemetry")]
+        {
+            use tracing::Instrument;
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+            let span = tracing::info_span!("session.handle_uni_stream", kind = ?envelope.body);
+            if let (Some(trace_id), Some(span_id)) = (&envelope.trace_id, &envelope.span_id) {
+                if let Some(parent_cx) =
+                    crate::core::telemetry::remote_parent_context(trace_id, span_id)
+                {
+                    span.set_parent(parent_cx);
+                }
+            }
+
+            async { state.lock().await.dispatch(&envelope.body) }
+                .instrument(span)
+                .await;
+        }
+
+        #[cfg(not(feature = "telemetry"))]
+        state.lock().await.dispatch(&envelope.body);
+
+        #[cfg(feature = "telemetry")]
+        let header_bytes = envelope.to_bytes()?.len() as u64;
+
+        let _body_bytes = deliver_body(state, envelope.body.clone(), recv_stream).await?;
+
+        #[cfg(feature = "telemetry")]
+        crate::core::telemetry::record_received(
+            &envelope.body,
+            header_bytes + _body_bytes,
+            started_at.elapsed(),
+        );
+
+        Ok(())
+    }
+
+    /// Handle an incoming bidirectional stream: requests are dispatched and
+    /// answered on the same stream, responses are routed to the caller
+    /// awaiting them in `pending_requests`.
+    async fn handle_bi_stream(
+        state: Arc<Mutex<SessionState>>,
+        mut send_stream: iroh::endpoint::SendStream,
+        mut recv_stream: iroh::endpoint::RecvStream,
+    ) -> Result<()> {
+        let envelope = SessionState::read_envelope(&mut recv_stream).await?;
+        debug!("Received {:?} envelope: {:?}", envelope.kind, envelope.body);
+
+        deliver_body(state.clone(), envelope.body.clone(), recv_stream).await?;
+
+        match envelope.kind {
+            EnvelopeKind::Request => {
+                let state_lock = state.lock().await;
+                state_lock.dispatch(&envelope.body);
+                let reply_body = match &state_lock.request_handler {
+                    Some(handler) => handler(&envelope.body),
+                    None => {
+                        warn!("No request handler installed; echoing request back to peer");
+                        envelope.body.clone()
+                    }
+                };
+                drop(state_lock);
+
+                let reply = Envelope::new(envelope.id, EnvelopeKind::Response, reply_body);
+                SessionState::write_header(&mut send_stream, &reply).await?;
+                send_stream
+                    .write_all(&0u32.to_be_bytes())
+                    .await
+                    .context("Failed to write body terminator")?;
+                send_stream
+                    .finish()
+                    .await
+                    .context("Failed to finish response stream")?;
+            }
+            EnvelopeKind::Response => {
+                let pending = state.lock().await.pending_requests.clone();
+                if let Some(tx) = pending.lock().await.remove(&envelope.id) {
+                    let _ = tx.send(envelope.body);
+                }
+                send_stream
+                    .finish()
+                    .await
+                    .context("Failed to finish response stream")?;
+            }
+            EnvelopeKind::Notify => {
+                state.lock().await.dispatch(&envelope.body);
+                send_stream
+                    .finish()
+                    .await
+                    .context("Failed to finish stream")?;
             }
         }
 
@@ -207,41 +794,153 @@ impl ProtocolHandler for SessionHandler {
                 let mut state = self.state.lock().await;
                 state.peer_id = Some(conn.remote_node_id().expect("peer id"));
                 state.connection = Some(Arc::new(Mutex::new(conn.clone())));
-                
+
                 // Notify frontend
-                if let Some(handle) = &state.app_handle {
-                    let _ = handle.emit_event("session-connected");
+                state.emit_status("session-connected");
+
+                #[cfg(feature = "control-socket")]
+                {
+                    let name = state.peer_id.map(|id| id.to_string()).unwrap_or_default();
+                    match crate::core::control::ControlSocket::start(self.state.clone(), &name).await {
+                        Ok(socket) => state.control_socket = Some(socket),
+                        Err(e) => warn!("Failed to start control socket: {}", e),
+                    }
                 }
             }
 
-            // Handle incoming streams
-            loop {
-                tokio::select! {
-                    stream = conn.accept_uni() => {
-                        match stream {
-                            Ok(recv_stream) => {
-                                let state = self.state.clone();
-                                tokio::spawn(async move {
-                                    if let Err(e) = Self::handle_uni_stream(state, recv_stream).await {
-                                        error!("Error handling stream: {}", e);
-                                    }
-                                });
+            let keepalive_task = tokio::spawn(run_keepalive(self.state.clone()));
+            run_stream_loop(self.state.clone(), conn).await;
+            keepalive_task.abort();
+
+            // This is an intentional asymmetry, not a partial implementation of
+            // reconnect-with-backoff: only `connect_session`/`run_session_supervisor`
+            // (the dialing side) redials on a transport error, because only it
+            // holds a `NodeAddr` to redial. An accepted connection has no such
+            // address, so there is nothing for this side to reconnect *to* --
+            // it tears down and waits for the peer to redial, which the router
+            // surfaces here as a fresh `accept` call.
+            let mut state = self.state.lock().await;
+            state.connection = None;
+            state.emit_status("session-disconnected");
+
+            #[cfg(feature = "control-socket")]
+            if let Some(socket) = state.control_socket.take() {
+                socket.shutdown();
+            }
+
+            info!("Session connection closed");
+            Ok(())
+        }
+    }
+}
+
+/// Hand a received body off to the handler installed via
+/// [`SessionState::set_body_handler`], or drain and discard it if none is
+/// installed. Returns the number of body bytes consumed, for telemetry; a
+/// handler may keep streaming the body in the background after returning, so
+/// `0` is reported for that case rather than a misleading guess.
+async fn deliver_body(
+    state: Arc<Mutex<SessionState>>,
+    message: SessionMessage,
+    recv_stream: iroh::endpoint::RecvStream,
+) -> Result<u64> {
+    let body_handler = state.lock().await.body_handler.clone();
+    let body = BodyStream::new(recv_stream);
+
+    match body_handler {
+        Some(handler) => {
+            handler(message, body);
+            Ok(0)
+        }
+        None => body.drain().await.context("Failed to drain message body"),
+    }
+}
+
+/// Service `conn`'s incoming uni/bi streams until the transport errors or
+/// `SessionState::close` signals via `close_notify`. Each stream is handled
+/// on a task tracked in `stream_tasks`, so `close()` can wait for in-flight
+/// ones to finish instead of cutting them off.
+async fn run_stream_loop(state: Arc<Mutex<SessionState>>, conn: iroh::endpoint::Connection) {
+    let (close_notify, stream_tasks) = {
+        let state = state.lock().await;
+        (state.close_notify.clone(), state.stream_tasks.clone())
+    };
+
+    loop {
+        tokio::select! {
+            _ = close_notify.notified() => {
+                debug!("Stream loop stopping: session closing");
+                break;
+            }
+            stream = conn.accept_uni() => {
+                match stream {
+                    Ok(recv_stream) => {
+                        let state = state.clone();
+                        stream_tasks.lock().await.spawn(async move {
+                            if let Err(e) = SessionHandler::handle_uni_stream(state, recv_stream).await {
+                                error!("Error handling stream: {}", e);
                             }
-                            Err(e) => {
-                                warn!("Error accepting stream: {}", e);
-                                break;
+                        });
+                    }
+                    Err(e) => {
+                        warn!("Error accepting stream: {}", e);
+                        break;
+                    }
+                }
+            }
+            stream = conn.accept_bi() => {
+                match stream {
+                    Ok((send_stream, recv_stream)) => {
+                        let state = state.clone();
+                        stream_tasks.lock().await.spawn(async move {
+                            if let Err(e) = SessionHandler::handle_bi_stream(state, send_stream, recv_stream).await {
+                                error!("Error handling bidirectional stream: {}", e);
                             }
-                        }
+                        });
+                    }
+                    Err(e) => {
+                        warn!("Error accepting bidirectional stream: {}", e);
+                        break;
                     }
                 }
             }
+        }
+    }
+}
 
-            info!("Session connection closed");
-            Ok(())
+/// Send a `Ping` on `KEEPALIVE_INTERVAL` to catch half-open connections
+/// proactively, until the session closes or a ping fails to send.
+async fn run_keepalive(state: Arc<Mutex<SessionState>>) {
+    let mut ticker = tokio::time::interval(KEEPALIVE_INTERVAL);
+    ticker.tick().await; // first tick fires immediately
+
+    loop {
+        ticker.tick().await;
+
+        let state_lock = state.lock().await;
+        if state_lock.closing.load(Ordering::SeqCst) {
+            break;
+        }
+        let result = state_lock.send_message(SessionMessage::Ping).await;
+        drop(state_lock);
+
+        if let Err(e) = result {
+            warn!("Keepalive ping failed: {}", e);
+            break;
         }
     }
 }
 
+/// Sleep for `backoff` plus under 250 ms of jitter, to avoid
+/// every reconnecting peer retrying in lockstep.
+async fn sleep_with_jitter(backoff: Duration) {
+    let jitter_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_millis() % 250)
+        .unwrap_or(0);
+    tokio::time::sleep(backoff + Duration::from_millis(u64::from(jitter_ms))).await;
+}
+
 /// Start a session as the initiator (connects to a ticket)
 pub async fn connect_session(
     ticket: String,
@@ -250,25 +949,27 @@ pub async fn connect_session(
     info!("Connecting to session with ticket");
 
     // Parse ticket
-    let ticket: iroh_blobs::ticket::BlobTicket = ticket
+    let parsed_ticket: iroh_blobs::ticket::BlobTicket = ticket
         .parse()
         .context("Failed to parse session ticket")?;
 
-    let node_addr = ticket.node_addr().clone();
+    let node_addr = parsed_ticket.node_addr().clone();
 
     // Create endpoint
     let secret_key = crate::core::types::get_or_create_secret()?;
-    let endpoint = iroh::Endpoint::builder()
-        .alpns(vec![SESSION_ALPN.to_vec()])
-        .secret_key(secret_key)
-        .relay_mode(iroh::RelayMode::Default)
-        .bind()
-        .await
-        .context("Failed to bind endpoint")?;
+    let endpoint = Arc::new(
+        iroh::Endpoint::builder()
+            .alpns(vec![SESSION_ALPN.to_vec()])
+            .secret_key(secret_key)
+            .relay_mode(iroh::RelayMode::Default)
+            .bind()
+            .await
+            .context("Failed to bind endpoint")?,
+    );
 
     // Connect to peer
     let conn = endpoint
-        .connect(node_addr, SESSION_ALPN)
+        .connect(node_addr.clone(), SESSION_ALPN)
         .await
         .context("Failed to connect to peer")?;
 
@@ -280,34 +981,71 @@ pub async fn connect_session(
         let mut state_lock = state.lock().await;
         state_lock.peer_id = Some(conn.remote_node_id().expect("peer id"));
         state_lock.connection = Some(Arc::new(Mutex::new(conn.clone())));
-        
+
         // Notify frontend
-        if let Some(handle) = &state_lock.app_handle {
-            let _ = handle.emit_event("session-connected");
+        state_lock.emit_status("session-connected");
+
+        #[cfg(feature = "control-socket")]
+        {
+            let name = state_lock.peer_id.map(|id| id.to_string()).unwrap_or_default();
+            match crate::core::control::ControlSocket::start(state.clone(), &name).await {
+                Ok(socket) => state_lock.control_socket = Some(socket),
+                Err(e) => warn!("Failed to start control socket: {}", e),
+            }
         }
     }
 
-    // Spawn task to handle incoming streams
-    let state_clone = state.clone();
-    tokio::spawn(async move {
-        loop {
-            match conn.accept_uni().await {
-                Ok(recv_stream) => {
-                    let state = state_clone.clone();
-                    tokio::spawn(async move {
-                        if let Err(e) = SessionHandler::handle_uni_stream(state, recv_stream).await
-                        {
-                            error!("Error handling stream: {}", e);
-                        }
-                    });
-                }
+    // Drive streams and, on a transport error, reconnect with backoff until
+    // `SessionState::close` is called.
+    tokio::spawn(run_session_supervisor(state.clone(), endpoint, node_addr, conn));
+
+    Ok(state)
+}
+
+/// Service `conn`'s streams; when the transport drops, redial `node_addr`
+/// with exponential backoff and resume, until `SessionState::close` is
+/// called.
+async fn run_session_supervisor(
+    state: Arc<Mutex<SessionState>>,
+    endpoint: Arc<iroh::Endpoint>,
+    node_addr: iroh::NodeAddr,
+    mut conn: iroh::endpoint::Connection,
+) {
+    loop {
+        let keepalive_task = tokio::spawn(run_keepalive(state.clone()));
+        run_stream_loop(state.clone(), conn.clone()).await;
+        keepalive_task.abort();
+
+        if state.lock().await.closing.load(Ordering::SeqCst) {
+            state.lock().await.emit_status("session-disconnected");
+            return;
+        }
+
+        state.lock().await.emit_status("session-reconnecting");
+
+        let mut backoff = INITIAL_RECONNECT_BACKOFF;
+        conn = loop {
+            if state.lock().await.closing.load(Ordering::SeqCst) {
+                state.lock().await.emit_status("session-disconnected");
+                return;
+            }
+
+            match endpoint.connect(node_addr.clone(), SESSION_ALPN).await {
+                Ok(new_conn) => break new_conn,
                 Err(e) => {
-                    warn!("Error accepting stream: {}", e);
-                    break;
+                    warn!("Reconnect attempt failed: {}", e);
+                    sleep_with_jitter(backoff).await;
+                    backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
                 }
             }
-        }
-    });
+        };
 
-    Ok(state)
+        let mut state_lock = state.lock().await;
+        state_lock.peer_id = Some(conn.remote_node_id().expect("peer id"));
+        state_lock.connection = Some(Arc::new(Mutex::new(conn.clone())));
+        state_lock.emit_status("session-connected");
+        drop(state_lock);
+
+        info!("Reconnected to session peer");
+    }
 }
@@ -0,0 +1,126 @@
+//! Optional OpenTelemetry instrumentation for the session protocol, enabled
+//! via the `telemetry` feature.
+//!
+//! Spans created by [`crate::core::session::SessionState::send_message`] and
+//! `SessionHandler::handle_uni_stream` link across the wire: the trace/span
+//! id active when a message is sent travels in its `Envelope`, so the
+//! receiver's span is created as a child of the sender's. That gives
+//! operators a single distributed trace for a file-offer -> accept ->
+//! transfer flow spanning two peers, exported via OTLP.
+
+use once_cell::sync::Lazy;
+use opentelemetry::metrics::{Counter, Histogram, Meter};
+use opentelemetry::trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState};
+use opentelemetry::{global, Context, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use std::time::Duration;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::core::session::SessionMessage;
+
+fn meter() -> Meter {
+    global::meter("alt-sendme/session")
+}
+
+static MESSAGES_SENT: Lazy<Counter<u64>> =
+    Lazy::new(|| meter().u64_counter("session.messages_sent").init());
+static MESSAGES_RECEIVED: Lazy<Counter<u64>> =
+    Lazy::new(|| meter().u64_counter("session.messages_received").init());
+static BYTES_SENT: Lazy<Counter<u64>> =
+    Lazy::new(|| meter().u64_counter("session.bytes_sent").init());
+static BYTES_RECEIVED: Lazy<Counter<u64>> =
+    Lazy::new(|| meter().u64_counter("session.bytes_received").init());
+static MESSAGE_LATENCY: Lazy<Histogram<f64>> = Lazy::new(|| {
+    meter()
+        .f64_histogram("session.message_latency_seconds")
+        .init()
+});
+
+/// Install the OTLP trace and metric pipelines. Call once at startup, before
+/// any session traffic flows.
+pub fn init(otlp_endpoint: &str) -> anyhow::Result<()> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(otlp_endpoint),
+        )
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+
+    let meter_provider = opentelemetry_otlp::new_pipeline()
+        .metrics(opentelemetry_sdk::runtime::Tokio)
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(otlp_endpoint),
+        )
+        .build()?;
+    global::set_meter_provider(meter_provider);
+
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .try_init()?;
+
+    Ok(())
+}
+
+fn kind_label(message: &SessionMessage) -> &'static str {
+    match message {
+        SessionMessage::Text { .. } => "text",
+        SessionMessage::FileOffer { .. } => "file_offer",
+        SessionMessage::FileAccept { .. } => "file_accept",
+        SessionMessage::CallSignal { .. } => "call_signal",
+        SessionMessage::Ping => "ping",
+        SessionMessage::Pong => "pong",
+    }
+}
+
+/// Record a message handed off to the transport.
+pub fn record_sent(message: &SessionMessage, bytes: u64) {
+    let kind = kind_label(message);
+    MESSAGES_SENT.add(1, &[KeyValue::new("kind", kind)]);
+    BYTES_SENT.add(bytes, &[KeyValue::new("kind", kind)]);
+}
+
+/// Record a message pulled off the transport, `latency` being the time since
+/// the header started arriving.
+pub fn record_received(message: &SessionMessage, bytes: u64, latency: Duration) {
+    let kind = kind_label(message);
+    MESSAGES_RECEIVED.add(1, &[KeyValue::new("kind", kind)]);
+    BYTES_RECEIVED.add(bytes, &[KeyValue::new("kind", kind)]);
+    MESSAGE_LATENCY.record(latency.as_secs_f64(), &[KeyValue::new("kind", kind)]);
+}
+
+/// The current span's trace/span id as hex, to embed in an outgoing envelope
+/// so the receiver's span can link back to this one.
+pub fn current_trace_context() -> (Option<String>, Option<String>) {
+    let context = tracing::Span::current().context();
+    let span_context = context.span().span_context().clone();
+    if span_context.is_valid() {
+        (
+            Some(span_context.trace_id().to_string()),
+            Some(span_context.span_id().to_string()),
+        )
+    } else {
+        (None, None)
+    }
+}
+
+/// Build a remote parent context from the trace/span id recovered from an
+/// incoming envelope, so the receiver's span can be linked to the sender's.
+pub fn remote_parent_context(trace_id: &str, span_id: &str) -> Option<Context> {
+    let trace_id = TraceId::from_hex(trace_id).ok()?;
+    let span_id = SpanId::from_hex(span_id).ok()?;
+    let span_context = SpanContext::new(
+        trace_id,
+        span_id,
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+
+    Some(Context::new().with_remote_span_context(span_context))
+}
@@ -3,6 +3,9 @@ pub mod core;
 pub use core::{
     send::{start_share},
     receive::{download},
-    session::{connect_session, SessionHandler, SessionMessage, SessionState, SESSION_ALPN},
+    session::{
+        connect_session, BodyHandler, BodyStream, RequestHandler, SessionHandler, SessionMessage,
+        SessionState, SESSION_ALPN,
+    },
     types::{SendResult, ReceiveResult, SendOptions, ReceiveOptions, RelayModeOption, AddrInfoOptions, AppHandle, EventEmitter},
 };